@@ -9,7 +9,9 @@ pub mod v1 {
 
     use std::{borrow::Cow, time::Duration};
 
-    use http::header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE};
+    use http::header::{
+        CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE, IF_MODIFIED_SINCE, IF_NONE_MATCH, RANGE,
+    };
     use ruma_common::{
         api::{request, response, Metadata},
         http_headers::ContentDisposition,
@@ -54,12 +56,61 @@ pub mod v1 {
             skip_serializing_if = "ruma_common::media::is_default_download_timeout"
         )]
         pub timeout_ms: Duration,
+
+        /// The value of the `Range` HTTP header.
+        ///
+        /// Allows the client to request only part of the media content, for example to resume an
+        /// interrupted download or to seek within the file. Supports a single `bytes=start-end`
+        /// range, a suffix range (`bytes=-500`) or an open-ended range (`bytes=500-`), resolved
+        /// against the content length with [`ruma_common::http_headers::parse_range`].
+        ///
+        /// There is currently no way for a server to act on this: answering with a partial body
+        /// requires a `206 Partial Content` status, and rejecting the range requires `416 Range
+        /// Not Satisfiable`, neither of which the `#[response]` derive can express yet. The
+        /// response-side `Accept-Ranges`/`Content-Range` headers are withheld until it can, so
+        /// this field doesn't yet advertise a capability the server can't honor correctly.
+        ///
+        /// See [MDN] for the syntax.
+        ///
+        /// [MDN]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Range#syntax
+        #[ruma_api(header = RANGE)]
+        pub range: Option<String>,
+
+        /// The value of the `If-None-Match` HTTP header.
+        ///
+        /// Allows the client to send back the `etag` of a previous response so the server can
+        /// recognize an unchanged fetch, checked with [`ruma_common::http_headers::etag_matches`].
+        /// Since Matrix media is immutable and content-addressed, a validator derived from the
+        /// media ID is always a strong one.
+        ///
+        /// There is currently no way for a server to act on a match: representing `304 Not
+        /// Modified` requires the `#[response]` derive to support an alternate status code, which
+        /// it can't yet. The response-side `ETag`/`Last-Modified` headers are withheld until it
+        /// can, so this field doesn't yet advertise a capability the server can't honor.
+        ///
+        /// See [MDN] for the syntax.
+        ///
+        /// [MDN]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/If-None-Match#syntax
+        #[ruma_api(header = IF_NONE_MATCH)]
+        pub if_none_match: Option<String>,
+
+        /// The value of the `If-Modified-Since` HTTP header.
+        ///
+        /// See [MDN] for the syntax.
+        ///
+        /// [MDN]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/If-Modified-Since#syntax
+        #[ruma_api(header = IF_MODIFIED_SINCE)]
+        pub if_modified_since: Option<String>,
     }
 
     /// Response type for the `get_media_content_as_filename` endpoint.
     #[response(error = crate::Error)]
     pub struct Response {
         /// The content that was previously uploaded.
+        ///
+        /// This buffers the whole file in memory. A streaming alternative (so servers can proxy
+        /// remote content without materializing it) would require a streaming `raw_body` variant
+        /// in `ruma-common`'s `api` traits; until that exists, `Vec<u8>` remains the only option.
         #[ruma_api(raw_body)]
         pub file: Vec<u8>,
 
@@ -101,6 +152,9 @@ pub mod v1 {
                 server_name,
                 filename,
                 timeout_ms: ruma_common::media::default_download_timeout(),
+                range: None,
+                if_none_match: None,
+                if_modified_since: None,
             }
         }
 
@@ -124,4 +178,13 @@ pub mod v1 {
             }
         }
     }
+
+    // TODO: `range` is resolved with `ruma_common::http_headers::parse_range` and
+    // `if_none_match` is checked with `ruma_common::http_headers::etag_matches`, but neither can
+    // be acted on end-to-end yet: that requires teaching the `#[response]` derive in
+    // `ruma-common`'s `api` module to emit an alternate status code per response variant, so a
+    // server can answer with `206 Partial Content`, `304 Not Modified`, or `416 Range Not
+    // Satisfiable` instead of the default `200 OK`. The response-side `Accept-Ranges`,
+    // `Content-Range`, `ETag` and `Last-Modified` headers are intentionally withheld until that
+    // mechanism exists, so as not to ship headers a `200 OK` response can't back up.
 }
@@ -0,0 +1,445 @@
+//! Types for HTTP headers that aren't covered by the `http` crate.
+
+use std::fmt;
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+/// The value of a [`Content-Disposition`] HTTP header.
+///
+/// [`Content-Disposition`]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Disposition
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContentDisposition {
+    /// The disposition type of the content.
+    pub disposition_type: ContentDispositionType,
+
+    /// The filename of the content.
+    ///
+    /// On construction, control characters are stripped since this is usually reflected from
+    /// user-controlled input (e.g. a path segment of a download endpoint) into an HTTP header.
+    pub filename: Option<String>,
+}
+
+impl ContentDisposition {
+    /// Creates a new `ContentDisposition` with the given disposition type.
+    pub fn new(disposition_type: ContentDispositionType) -> Self {
+        Self { disposition_type, filename: None }
+    }
+
+    /// Sets the filename of `self`.
+    pub fn with_filename(mut self, filename: Option<String>) -> Self {
+        self.filename = filename.map(|f| sanitize_filename(&f));
+        self
+    }
+}
+
+impl fmt::Display for ContentDisposition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.disposition_type)?;
+
+        if let Some(filename) = &self.filename {
+            // `filename` is the classic, ASCII-only parameter. Older clients that don't
+            // understand `filename*` fall back to this, so always send a (possibly lossy)
+            // ASCII approximation alongside the RFC 5987 extended value.
+            write!(f, "; filename=\"{}\"", escape_quoted_string(&ascii_fallback(filename)))?;
+
+            if !filename.is_ascii() {
+                write!(
+                    f,
+                    "; filename*={}''{}",
+                    Charset::Utf8,
+                    utf8_percent_encode(filename, EXT_VALUE_ENCODE_SET)
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for ContentDisposition {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(';').map(str::trim);
+        let disposition_type = parts.next().unwrap_or_default().parse().unwrap();
+
+        let mut filename = None;
+        let mut filename_ext = None;
+
+        for part in parts {
+            if let Some(value) = part.strip_prefix("filename*=") {
+                filename_ext = parse_extended_value(value);
+            } else if let Some(value) = part.strip_prefix("filename=") {
+                filename = Some(unescape_quoted_string(value.trim_matches('"')));
+            }
+        }
+
+        let filename = filename_ext.or(filename).map(|f| sanitize_filename(&f));
+
+        Ok(Self { disposition_type, filename })
+    }
+}
+
+/// The disposition type of a [`ContentDisposition`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ContentDispositionType {
+    /// `inline`
+    Inline,
+
+    /// `attachment`
+    Attachment,
+
+    /// A disposition type that is not known to ruma.
+    Unknown(String),
+}
+
+impl fmt::Display for ContentDispositionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Inline => write!(f, "inline"),
+            Self::Attachment => write!(f, "attachment"),
+            Self::Unknown(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl std::str::FromStr for ContentDispositionType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "inline" => Self::Inline,
+            "attachment" => Self::Attachment,
+            s => Self::Unknown(s.to_owned()),
+        })
+    }
+}
+
+/// The charset of an RFC 5987 extended value, as used in the `filename*` parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Charset {
+    /// `UTF-8`
+    Utf8,
+
+    /// `ISO-8859-1`
+    Iso8859_1,
+}
+
+impl fmt::Display for Charset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Utf8 => write!(f, "UTF-8"),
+            Self::Iso8859_1 => write!(f, "ISO-8859-1"),
+        }
+    }
+}
+
+/// The set of bytes that must be percent-encoded in an RFC 5987 `ext-value`.
+///
+/// This is everything outside of `attr-char`, i.e. everything but ASCII letters, digits and
+/// ``!#$&+-.^_`|~``.
+const EXT_VALUE_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'!')
+    .remove(b'#')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'+')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'^')
+    .remove(b'_')
+    .remove(b'`')
+    .remove(b'|')
+    .remove(b'~');
+
+/// Parses the value of a `filename*` parameter: `charset'language'value`.
+fn parse_extended_value(s: &str) -> Option<String> {
+    let mut parts = s.splitn(3, '\'');
+    let charset = parts.next()?;
+    let _language = parts.next()?;
+    let value = parts.next()?;
+
+    let decoded = percent_decode_str(value);
+    match charset.to_ascii_uppercase().as_str() {
+        "UTF-8" => decoded.decode_utf8().ok().map(|s| s.into_owned()),
+        // ISO-8859-1 maps byte-for-byte onto the first 256 Unicode scalar values.
+        "ISO-8859-1" => Some(decoded.map(|b| b as char).collect()),
+        _ => None,
+    }
+}
+
+/// Strips control characters out of a filename that may come from user input.
+fn sanitize_filename(filename: &str) -> String {
+    filename.chars().filter(|c| !c.is_control()).collect()
+}
+
+/// Produces a best-effort ASCII-only approximation of `filename`, for the benefit of clients
+/// that only understand the plain `filename` parameter.
+fn ascii_fallback(filename: &str) -> String {
+    if filename.is_ascii() {
+        filename.to_owned()
+    } else {
+        filename.chars().map(|c| if c.is_ascii() { c } else { '_' }).collect()
+    }
+}
+
+/// A single byte range, already resolved against a resource of a known length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ByteRange {
+    /// The first byte of the range, inclusive.
+    pub start: u64,
+
+    /// The last byte of the range, inclusive.
+    pub end: u64,
+}
+
+impl ByteRange {
+    /// The value to use for the `Content-Range` HTTP header of a `206 Partial Content` response
+    /// serving this range out of a resource of `complete_length` bytes.
+    pub fn content_range(&self, complete_length: u64) -> String {
+        format!("bytes {}-{}/{complete_length}", self.start, self.end)
+    }
+}
+
+/// The error returned by [`parse_range`] when the `Range` header can't be satisfied for a
+/// resource of a given length.
+///
+/// A server should respond to this with `416 Range Not Satisfiable`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RangeNotSatisfiable {
+    /// The full length of the resource, in bytes.
+    pub complete_length: u64,
+}
+
+impl RangeNotSatisfiable {
+    /// The value to use for the `Content-Range` HTTP header of the `416 Range Not Satisfiable`
+    /// response.
+    pub fn content_range(&self) -> String {
+        format!("bytes */{}", self.complete_length)
+    }
+}
+
+/// Parses the value of a `Range` HTTP header (without the leading `Range: `) and resolves it
+/// against a resource of `complete_length` bytes, following the standard algorithm of
+/// [RFC 9110 § 14.1.2]: `start-end` resolves to `[start, min(end, complete_length - 1)]`,
+/// `start-` resolves to `[start, complete_length - 1]`, and the suffix range `-suffix` resolves
+/// to `[complete_length - suffix, complete_length - 1]`, starting at `0` when `suffix` exceeds
+/// `complete_length`.
+///
+/// Only the unit `bytes` is supported. If the header contains a comma-separated list of ranges
+/// (multipart/byteranges), only the first one is resolved; the rest are ignored.
+///
+/// Returns [`RangeNotSatisfiable`] if the header is malformed, if `start > end`, or if
+/// `start >= complete_length`.
+///
+/// [RFC 9110 § 14.1.2]: https://httpwg.org/specs/rfc9110.html#rfc.section.14.1.2
+pub fn parse_range(range: &str, complete_length: u64) -> Result<ByteRange, RangeNotSatisfiable> {
+    let unsatisfiable = || RangeNotSatisfiable { complete_length };
+
+    if complete_length == 0 {
+        return Err(unsatisfiable());
+    }
+
+    let spec = range.strip_prefix("bytes=").ok_or_else(unsatisfiable)?;
+    let spec = spec.split(',').next().ok_or_else(unsatisfiable)?.trim();
+    let (start_s, end_s) = spec.split_once('-').ok_or_else(unsatisfiable)?;
+
+    let (start, end) = if start_s.is_empty() {
+        // `-suffix`: the last `suffix` bytes of the resource.
+        let suffix: u64 = end_s.parse().map_err(|_| unsatisfiable())?;
+        if suffix == 0 {
+            return Err(unsatisfiable());
+        }
+        (complete_length.saturating_sub(suffix), complete_length - 1)
+    } else {
+        let start: u64 = start_s.parse().map_err(|_| unsatisfiable())?;
+        let end = if end_s.is_empty() {
+            complete_length - 1
+        } else {
+            let end: u64 = end_s.parse().map_err(|_| unsatisfiable())?;
+            end.min(complete_length - 1)
+        };
+        (start, end)
+    };
+
+    if start > end || start >= complete_length {
+        return Err(unsatisfiable());
+    }
+
+    Ok(ByteRange { start, end })
+}
+
+/// Checks whether an `If-None-Match` header value matches a given `etag`, per
+/// [RFC 9110 § 13.1.2].
+///
+/// `if_none_match` may be a comma-separated list of entity tags, or `*`, which matches any
+/// `etag`. Per the spec, this comparison is weak: a `W/` prefix on either side is ignored.
+///
+/// [RFC 9110 § 13.1.2]: https://httpwg.org/specs/rfc9110.html#rfc.section.13.1.2
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if if_none_match.trim() == "*" {
+        return true;
+    }
+
+    let etag = etag.trim().trim_start_matches("W/").trim_matches('"');
+    if_none_match.split(',').map(str::trim).any(|candidate| {
+        candidate.trim_start_matches("W/").trim_matches('"') == etag
+    })
+}
+
+fn escape_quoted_string(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn unescape_quoted_string(s: &str) -> String {
+    s.replace("\\\"", "\"").replace("\\\\", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        etag_matches, parse_range, ByteRange, ContentDisposition, ContentDispositionType,
+        RangeNotSatisfiable,
+    };
+
+    #[test]
+    fn display_ascii_filename() {
+        let disposition =
+            ContentDisposition::new(ContentDispositionType::Attachment)
+                .with_filename(Some("cat.png".to_owned()));
+        assert_eq!(disposition.to_string(), "attachment; filename=\"cat.png\"");
+    }
+
+    #[test]
+    fn display_non_ascii_filename_includes_extended_value() {
+        let disposition = ContentDisposition::new(ContentDispositionType::Inline)
+            .with_filename(Some("café.png".to_owned()));
+        assert_eq!(
+            disposition.to_string(),
+            "inline; filename=\"caf_.png\"; filename*=UTF-8''caf%C3%A9.png"
+        );
+    }
+
+    #[test]
+    fn roundtrip_extended_value() {
+        let original = ContentDisposition::new(ContentDispositionType::Attachment)
+            .with_filename(Some("日本語.txt".to_owned()));
+        let parsed: ContentDisposition = original.to_string().parse().unwrap();
+        assert_eq!(parsed.filename.as_deref(), Some("日本語.txt"));
+        assert_eq!(parsed.disposition_type, ContentDispositionType::Attachment);
+    }
+
+    #[test]
+    fn roundtrip_filename_with_quotes_and_backslashes() {
+        let original = ContentDisposition::new(ContentDispositionType::Attachment)
+            .with_filename(Some(r#"weird "name"\thing.txt"#.to_owned()));
+        let serialized = original.to_string();
+        assert_eq!(serialized, r#"attachment; filename="weird \"name\"\\thing.txt""#);
+
+        let parsed: ContentDisposition = serialized.parse().unwrap();
+        assert_eq!(parsed.filename.as_deref(), Some(r#"weird "name"\thing.txt"#));
+    }
+
+    #[test]
+    fn from_str_sanitizes_control_characters() {
+        let parsed: ContentDisposition =
+            "attachment; filename=\"evil\x0a.txt\"".parse().unwrap();
+        assert_eq!(parsed.filename.as_deref(), Some("evil.txt"));
+    }
+
+    #[test]
+    fn parse_range_start_end() {
+        assert_eq!(parse_range("bytes=0-499", 1000), Ok(ByteRange { start: 0, end: 499 }));
+    }
+
+    #[test]
+    fn parse_range_open_ended() {
+        assert_eq!(parse_range("bytes=500-", 1000), Ok(ByteRange { start: 500, end: 999 }));
+    }
+
+    #[test]
+    fn parse_range_suffix() {
+        assert_eq!(parse_range("bytes=-500", 1000), Ok(ByteRange { start: 500, end: 999 }));
+    }
+
+    #[test]
+    fn parse_range_suffix_larger_than_resource_clamps_to_whole_resource() {
+        assert_eq!(parse_range("bytes=-10000", 1000), Ok(ByteRange { start: 0, end: 999 }));
+    }
+
+    #[test]
+    fn parse_range_end_clamped_to_complete_length() {
+        assert_eq!(parse_range("bytes=900-10000", 1000), Ok(ByteRange { start: 900, end: 999 }));
+    }
+
+    #[test]
+    fn parse_range_only_first_of_multiple_ranges() {
+        assert_eq!(parse_range("bytes=0-99,200-299", 1000), Ok(ByteRange { start: 0, end: 99 }));
+    }
+
+    #[test]
+    fn parse_range_start_at_or_past_complete_length_is_unsatisfiable() {
+        assert_eq!(
+            parse_range("bytes=1000-1999", 1000),
+            Err(RangeNotSatisfiable { complete_length: 1000 })
+        );
+    }
+
+    #[test]
+    fn parse_range_start_after_end_is_unsatisfiable() {
+        assert_eq!(
+            parse_range("bytes=500-100", 1000),
+            Err(RangeNotSatisfiable { complete_length: 1000 })
+        );
+    }
+
+    #[test]
+    fn parse_range_malformed_is_unsatisfiable() {
+        assert_eq!(
+            parse_range("not a range", 1000),
+            Err(RangeNotSatisfiable { complete_length: 1000 })
+        );
+    }
+
+    #[test]
+    fn parse_range_empty_resource_is_unsatisfiable() {
+        assert_eq!(
+            parse_range("bytes=0-499", 0),
+            Err(RangeNotSatisfiable { complete_length: 0 })
+        );
+    }
+
+    #[test]
+    fn content_range_headers() {
+        let range = ByteRange { start: 0, end: 499 };
+        assert_eq!(range.content_range(1000), "bytes 0-499/1000");
+        assert_eq!(RangeNotSatisfiable { complete_length: 1000 }.content_range(), "bytes */1000");
+    }
+
+    #[test]
+    fn etag_matches_exact() {
+        assert!(etag_matches("\"abc123\"", "\"abc123\""));
+    }
+
+    #[test]
+    fn etag_matches_wildcard() {
+        assert!(etag_matches("*", "\"anything\""));
+    }
+
+    #[test]
+    fn etag_matches_weak_comparison() {
+        assert!(etag_matches("W/\"abc123\"", "\"abc123\""));
+    }
+
+    #[test]
+    fn etag_matches_one_of_a_list() {
+        assert!(etag_matches("\"nope\", \"abc123\", \"also-nope\"", "\"abc123\""));
+    }
+
+    #[test]
+    fn etag_does_not_match() {
+        assert!(!etag_matches("\"abc123\"", "\"xyz789\""));
+    }
+}
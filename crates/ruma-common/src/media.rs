@@ -0,0 +1,133 @@
+//! Common types for the media repository.
+
+use std::{borrow::Cow, time::Duration};
+
+use crate::http_headers::{ContentDisposition, ContentDispositionType};
+
+/// The default value for the `timeout_ms` query parameter of media download requests, in
+/// milliseconds.
+const DEFAULT_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// The default value used for `timeout_ms` on download requests.
+pub fn default_download_timeout() -> Duration {
+    DEFAULT_DOWNLOAD_TIMEOUT
+}
+
+/// Whether the given `Duration` is the default value for `timeout_ms` on download requests.
+pub fn is_default_download_timeout(timeout: &Duration) -> bool {
+    *timeout == DEFAULT_DOWNLOAD_TIMEOUT
+}
+
+/// The recommended headers to serve a piece of media with, given its content type.
+///
+/// Returned by [`content_type_policy`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MediaContentPolicy {
+    /// The content type to serve.
+    ///
+    /// This is the one passed to [`content_type_policy`], or `application/octet-stream` if
+    /// `None` was given.
+    pub content_type: Cow<'static, str>,
+
+    /// The `Content-Disposition` to serve the content with.
+    pub content_disposition: ContentDisposition,
+
+    /// The value to use for the `Cross-Origin-Resource-Policy` header.
+    pub cross_origin_resource_policy: Cow<'static, str>,
+
+    /// The value to use for the `Cache-Control` header.
+    pub cache_control: Cow<'static, str>,
+}
+
+/// Content types that are safe to render inline in a browser without risking script execution
+/// or other active content, per the Matrix spec's media safety recommendations.
+const INLINE_SAFE_CONTENT_TYPES: &[&str] = &[
+    "image/jpeg",
+    "image/gif",
+    "image/png",
+    "image/apng",
+    "image/webp",
+    "image/avif",
+    "audio/mp4",
+    "audio/webm",
+    "audio/mpeg",
+    "audio/ogg",
+    "audio/wave",
+    "audio/wav",
+    "audio/x-wav",
+    "audio/x-pn-wav",
+    "audio/flac",
+    "audio/x-flac",
+    "video/mp4",
+    "video/webm",
+    "video/ogg",
+    "video/quicktime",
+    "text/plain",
+];
+
+/// Returns the recommended [`MediaContentPolicy`] — content type, `Content-Disposition`,
+/// `Cross-Origin-Resource-Policy` and `Cache-Control` — to serve a downloaded piece of media
+/// with.
+///
+/// Content types that are not on a small allowlist of types considered safe to render inline
+/// (a handful of image, audio, video and plain-text types) are downgraded to
+/// `Content-Disposition: attachment`, so that a browser navigating directly to the media never
+/// executes it as a script, applet, or other active content. This centralizes the guidance from
+/// the Matrix spec's "Content-Disposition" and "Cross-Origin-Resource-Policy" recommendations for
+/// the media download endpoints, instead of every homeserver reimplementing the same allowlist.
+///
+/// `filename` is the filename to use for the `Content-Disposition` header, if any; it is passed
+/// through [`ContentDisposition`], which strips control characters and percent-encodes it if
+/// necessary.
+pub fn content_type_policy(
+    content_type: Option<&str>,
+    filename: Option<String>,
+) -> MediaContentPolicy {
+    let content_type = content_type.unwrap_or("application/octet-stream");
+
+    let disposition_type = if INLINE_SAFE_CONTENT_TYPES.contains(&content_type) {
+        ContentDispositionType::Inline
+    } else {
+        ContentDispositionType::Attachment
+    };
+
+    MediaContentPolicy {
+        content_type: Cow::Owned(content_type.to_owned()),
+        content_disposition: ContentDisposition::new(disposition_type).with_filename(filename),
+        cross_origin_resource_policy: Cow::Borrowed("cross-origin"),
+        cache_control: Cow::Borrowed("public, max-age=604800, immutable"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::content_type_policy;
+    use crate::http_headers::ContentDispositionType;
+
+    #[test]
+    fn known_safe_type_is_inline() {
+        let policy = content_type_policy(Some("image/png"), Some("cat.png".to_owned()));
+        assert_eq!(policy.content_disposition.disposition_type, ContentDispositionType::Inline);
+    }
+
+    #[test]
+    fn unknown_type_is_downgraded_to_attachment() {
+        let policy =
+            content_type_policy(Some("application/javascript"), Some("evil.js".to_owned()));
+        assert_eq!(
+            policy.content_disposition.disposition_type,
+            ContentDispositionType::Attachment
+        );
+    }
+
+    #[test]
+    fn missing_type_defaults_to_octet_stream_and_attachment() {
+        let policy = content_type_policy(None, None);
+        assert_eq!(policy.content_type, "application/octet-stream");
+        assert_eq!(
+            policy.content_disposition.disposition_type,
+            ContentDispositionType::Attachment
+        );
+    }
+}